@@ -2,7 +2,7 @@
 //! required between two strings, but most users should prefer the functionality in the crate root.
 
 use sliding_windows::{IterExt, Storage};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use util::round_score_decimal;
 
 const SPACE: char = ' ';
@@ -72,15 +72,84 @@ impl SimilarityAlgorithm for SorensenDice {
 }
 
 /// Levenshtein edit distance algorithm.
-// Add a hidden, unused param to prevent direct construction.
-pub struct Levenshtein(bool);
+///
+/// Keeps a single scratch row around between calls (see [`new`](SimilarityAlgorithm::new)) so repeated calls don't
+/// pay for a fresh `(n+1)*(m+1)` allocation every time. When the shorter of the two strings fits in a 64-bit word,
+/// this also switches to Myers' bit-parallel algorithm, which is faster and doesn't need the scratch row at all.
+pub struct Levenshtein {
+    row: Vec<usize>,
+}
+impl Levenshtein {
+    // Bit-parallel edit distance (Myers, 1999). Requires `pattern.len() <= 64` so the match masks fit in a u64.
+    fn myers_distance(pattern: &[char], text: &[char]) -> usize {
+        let width = pattern.len();
+
+        let mut peq: HashMap<char, u64> = HashMap::with_capacity(width);
+        for (i, &c) in pattern.iter().enumerate() {
+            *peq.entry(c).or_insert(0) |= 1u64 << i;
+        }
+
+        let mut pv: u64 = if width == 64 { !0u64 } else { (1u64 << width) - 1 };
+        let mut mv: u64 = 0;
+        let mut score = width;
+        let last_bit = 1u64 << (width - 1);
+
+        for c in text {
+            let eq = *peq.get(c).unwrap_or(&0);
+
+            let xv = eq | mv;
+            let xh = (((eq & pv).wrapping_add(pv)) ^ pv) | eq;
+            let mut ph = mv | !(xh | pv);
+            let mut mh = pv & xh;
+
+            if ph & last_bit != 0 {
+                score += 1;
+            } else if mh & last_bit != 0 {
+                score -= 1;
+            }
+
+            ph = (ph << 1) | 1;
+            mh <<= 1;
+            pv = mh | !(xv | ph);
+            mv = ph & xv;
+        }
+
+        score
+    }
+
+    // Classic DP kept to a single row plus a diagonal temporary, falling back for strings too long for the bit-parallel path above. Reuses `self.row`
+    // across calls instead of allocating a fresh matrix.
+    fn linear_distance(&mut self, s_chars: &[char], t_chars: &[char]) -> usize {
+        use std::cmp::min;
+
+        let (n, m) = (s_chars.len(), t_chars.len());
+
+        self.row.clear();
+        self.row.extend(0..=m);
+
+        for i in 1..n + 1 {
+            let mut diag = self.row[0];
+            self.row[0] = i;
+
+            for j in 1..m + 1 {
+                let cost = if s_chars[i - 1] == t_chars[j - 1] { 0 } else { 1 };
+                let prev_diag = self.row[j];
+
+                self.row[j] = min(min(self.row[j] + 1, self.row[j - 1] + 1), diag + cost);
+                diag = prev_diag;
+            }
+        }
+
+        self.row[m]
+    }
+}
 impl SimilarityAlgorithm for Levenshtein {
     fn new() -> Levenshtein {
-        Levenshtein(false)
+        Levenshtein { row: Vec::new() }
     }
 
     fn get_similarity(&mut self, s: &str, t: &str) -> f32 {
-        use std::cmp::{min, max};
+        use std::cmp::max;
 
         let n = s.len();
         let m = t.len();
@@ -95,16 +164,173 @@ impl SimilarityAlgorithm for Levenshtein {
             // For a description of the algorithm, see
             // https://people.cs.pitt.edu/~kirk/cs1501/Pruhs/Spring2006/assignments/editdistance/Levenshtein%20Distance.htm
 
-            // Get character vector for both strings
             let s_chars = s.chars().collect::<Vec<char>>();
             let t_chars = t.chars().collect::<Vec<char>>();
 
-            // Build the matrix
-            let mut rows: Vec<Vec<usize>> = Vec::with_capacity(m);
-            for i in 0..n + 1 {
-                let mut row = Vec::with_capacity(n + 1);
-                for j in 0..m + 1 {
-                    if i == 0 { 
+            let (shorter, longer) = if s_chars.len() <= t_chars.len() {
+                (&s_chars, &t_chars)
+            } else {
+                (&t_chars, &s_chars)
+            };
+
+            let distance = if shorter.len() <= 64 {
+                Levenshtein::myers_distance(shorter, longer)
+            } else {
+                self.linear_distance(longer, shorter)
+            };
+
+            round_score_decimal(1f32 - (distance as f32 / max(longer.len(), shorter.len()) as f32))
+        }
+    }
+}
+
+/// Jaro similarity algorithm. Performs better than edit-distance based algorithms on short strings with transposed
+/// characters, such as typos.
+// Add a hidden, unused param to prevent direct construction.
+pub struct Jaro(bool);
+impl Jaro {
+    /// Computes the raw (unrounded) Jaro similarity of two strings, shared with [`JaroWinkler`](JaroWinkler).
+    fn jaro_similarity(a: &str, b: &str) -> f32 {
+        use std::cmp::{min, max};
+
+        let a_chars = a.chars().collect::<Vec<char>>();
+        let b_chars = b.chars().collect::<Vec<char>>();
+        let len1 = a_chars.len();
+        let len2 = b_chars.len();
+
+        if len1 == 0 || len2 == 0 {
+            return 0f32;
+        }
+
+        let window = (max(len1, len2) / 2).saturating_sub(1);
+
+        let mut a_matched = vec![false; len1];
+        let mut b_matched = vec![false; len2];
+        let mut matches = 0usize;
+
+        for i in 0..len1 {
+            let lo = i.saturating_sub(window);
+            let hi = min(i + window, len2 - 1);
+            for j in lo..=hi {
+                if b_matched[j] || a_chars[i] != b_chars[j] {
+                    continue;
+                }
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+
+        if matches == 0 {
+            return 0f32;
+        }
+
+        let mut transpositions = 0usize;
+        let mut b_idx = 0;
+        for i in 0..len1 {
+            if !a_matched[i] {
+                continue;
+            }
+            while !b_matched[b_idx] {
+                b_idx += 1;
+            }
+            if a_chars[i] != b_chars[b_idx] {
+                transpositions += 1;
+            }
+            b_idx += 1;
+        }
+        let t = (transpositions / 2) as f32;
+        let m = matches as f32;
+
+        (m / len1 as f32 + m / len2 as f32 + (m - t) / m) / 3f32
+    }
+}
+impl SimilarityAlgorithm for Jaro {
+    fn new() -> Jaro {
+        Jaro(false)
+    }
+
+    fn get_similarity(&mut self, a: &str, b: &str) -> f32 {
+        if a == b {
+            1f32
+        } else if a.len() == 1 && b.len() == 1 {
+            0f32
+        } else if a.len() == 0 || b.len() == 0 {
+            0f32
+        } else {
+            round_score_decimal(Jaro::jaro_similarity(a, b))
+        }
+    }
+}
+
+/// Jaro-Winkler similarity algorithm. Extends [`Jaro`](Jaro) with a bonus for strings which share a common prefix.
+// Add a hidden, unused param to prevent direct construction.
+pub struct JaroWinkler(bool);
+impl SimilarityAlgorithm for JaroWinkler {
+    fn new() -> JaroWinkler {
+        JaroWinkler(false)
+    }
+
+    fn get_similarity(&mut self, a: &str, b: &str) -> f32 {
+        if a == b {
+            1f32
+        } else if a.len() == 1 && b.len() == 1 {
+            0f32
+        } else if a.len() == 0 || b.len() == 0 {
+            0f32
+        } else {
+            const PREFIX_SCALE: f32 = 0.1;
+            const MAX_PREFIX_LEN: usize = 4;
+
+            let jaro = Jaro::jaro_similarity(a, b);
+
+            let prefix_len = a
+                .chars()
+                .zip(b.chars())
+                .take(MAX_PREFIX_LEN)
+                .take_while(|(ac, bc)| ac == bc)
+                .count();
+
+            round_score_decimal(jaro + prefix_len as f32 * PREFIX_SCALE * (1f32 - jaro))
+        }
+    }
+}
+
+/// Optimal String Alignment (restricted Damerau-Levenshtein) edit distance algorithm. Like [`Levenshtein`](Levenshtein),
+/// but additionally treats a transposition of two adjacent characters as a single edit rather than two.
+// Add a hidden, unused param to prevent direct construction.
+pub struct OptimalStringAlignment(bool);
+impl SimilarityAlgorithm for OptimalStringAlignment {
+    fn new() -> OptimalStringAlignment {
+        OptimalStringAlignment(false)
+    }
+
+    fn get_similarity(&mut self, s: &str, t: &str) -> f32 {
+        use std::cmp::{min, max};
+
+        let n = s.len();
+        let m = t.len();
+
+        if s == t {
+            1f32
+        } else if n == 1 && m == 1 {
+            0f32
+        } else if n == 0 || m == 0 {
+            0f32
+        } else {
+            // Extends the Levenshtein matrix with a transposition case; see
+            // https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+
+            let s_chars = s.chars().collect::<Vec<char>>();
+            let t_chars = t.chars().collect::<Vec<char>>();
+            let (char_n, char_m) = (s_chars.len(), t_chars.len());
+
+            let mut rows: Vec<Vec<usize>> = Vec::with_capacity(char_n + 1);
+            for i in 0..char_n + 1 {
+                let mut row = Vec::with_capacity(char_m + 1);
+                for j in 0..char_m + 1 {
+                    if i == 0 {
                         row.push(j);
                     } else if j == 0 {
                         row.push(i);
@@ -115,24 +341,188 @@ impl SimilarityAlgorithm for Levenshtein {
                 rows.push(row);
             }
 
-            // Iterate over the strings
-            for i in 1..n + 1 {
-                for j in 1..m + 1 {
+            for i in 1..char_n + 1 {
+                for j in 1..char_m + 1 {
                     let cost = if s_chars[i - 1] == t_chars[j - 1] {
                         0
                     } else {
                         1
                     };
 
-                    let above = 1 + rows[i-1][j];
-                    let left = 1 + rows[i][j-1];
-                    let diag = cost + rows[i-1][j-1];
+                    let above = 1 + rows[i - 1][j];
+                    let left = 1 + rows[i][j - 1];
+                    let diag = cost + rows[i - 1][j - 1];
+
+                    let mut best = min(min(above, left), diag);
 
-                    rows[i][j] = min(min(above, left), diag);
+                    if i > 1 && j > 1 && s_chars[i - 1] == t_chars[j - 2] && s_chars[i - 2] == t_chars[j - 1] {
+                        best = min(best, 1 + rows[i - 2][j - 2]);
+                    }
+
+                    rows[i][j] = best;
                 }
             }
 
-            round_score_decimal(1f32 - (rows[n][m] as f32 / max(n, m) as f32))
+            round_score_decimal(1f32 - (rows[char_n][char_m] as f32 / max(char_n, char_m) as f32))
+        }
+    }
+}
+
+/// Character classes used by [`fzf_match`](fzf_match) to decide where a match "begins a word" for bonus purposes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Lower,
+    Upper,
+    Number,
+    Delimiter,
+    Whitespace,
+    NonWord,
+}
+
+fn classify(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c == '/' || c == ',' || c == ':' || c == ';' || c == '|' || c == '-' || c == '_' || c == '.' {
+        CharClass::Delimiter
+    } else if c.is_lowercase() {
+        CharClass::Lower
+    } else if c.is_uppercase() {
+        CharClass::Upper
+    } else if c.is_numeric() {
+        CharClass::Number
+    } else {
+        CharClass::NonWord
+    }
+}
+
+const FZF_SCORE_MATCH: i32 = 16;
+const FZF_GAP_PENALTY: i32 = 1;
+const FZF_BONUS_BOUNDARY: i32 = FZF_SCORE_MATCH / 2;
+const FZF_BONUS_NON_WORD: i32 = FZF_SCORE_MATCH / 2;
+const FZF_BONUS_CAMEL_123: i32 = FZF_BONUS_BOUNDARY - 1;
+const FZF_BONUS_CONSECUTIVE: i32 = FZF_SCORE_MATCH / 4;
+const FZF_BONUS_FIRST_CHAR_MULTIPLIER: i32 = 2;
+
+fn fzf_position_bonus(is_first: bool, prev_class: CharClass, cur_class: CharClass) -> i32 {
+    if is_first {
+        return FZF_BONUS_BOUNDARY * FZF_BONUS_FIRST_CHAR_MULTIPLIER;
+    }
+
+    match prev_class {
+        CharClass::Delimiter | CharClass::Whitespace => FZF_BONUS_BOUNDARY,
+        CharClass::NonWord => FZF_BONUS_NON_WORD,
+        CharClass::Lower if cur_class == CharClass::Upper => FZF_BONUS_CAMEL_123,
+        _ => 0,
+    }
+}
+
+/// Scores `needle` as a fuzzy subsequence of `candidate`, fzf-style: characters don't need to be contiguous, but
+/// matches that begin a word (start of string, after a delimiter/whitespace, a camelCase hump, after a non-word
+/// character) score higher, consecutive runs of matched characters also earn a (smaller) bonus, and gaps between
+/// matches are penalized. Returns `None` if either string is empty or `needle` is not a subsequence of `candidate` at
+/// all, otherwise the raw score
+/// together with the byte offsets in `candidate` that were matched, in order.
+pub fn fzf_match(needle: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    use std::cmp::max;
+
+    let needle_chars = needle.chars().collect::<Vec<char>>();
+    let cand_chars = candidate.chars().collect::<Vec<char>>();
+    let cand_byte_offsets = candidate.char_indices().map(|(i, _)| i).collect::<Vec<usize>>();
+
+    let n = needle_chars.len();
+    let m = cand_chars.len();
+
+    if n == 0 || m == 0 || n > m {
+        return None;
+    }
+
+    let classes = cand_chars.iter().map(|&c| classify(c)).collect::<Vec<CharClass>>();
+    let bonus = (0..m)
+        .map(|j| {
+            let prev_class = if j == 0 { CharClass::Whitespace } else { classes[j - 1] };
+            fzf_position_bonus(j == 0, prev_class, classes[j])
+        })
+        .collect::<Vec<i32>>();
+
+    const NEG_INFINITY: i64 = i64::min_value() / 2;
+
+    // h[i][j]: best score aligning the first i needle chars within the first j candidate chars.
+    // m_score[i][j]: best score when needle char i is matched exactly at candidate position j - 1.
+    let mut h: Vec<Vec<i64>> = vec![vec![0i64; m + 1]; n + 1];
+    let mut m_score: Vec<Vec<i64>> = vec![vec![NEG_INFINITY; m + 1]; n + 1];
+    let mut matched_here: Vec<Vec<bool>> = vec![vec![false; m + 1]; n + 1];
+
+    for i in 1..n + 1 {
+        h[i][0] = NEG_INFINITY;
+    }
+
+    for i in 1..n + 1 {
+        for j in 1..m + 1 {
+            if needle_chars[i - 1] == cand_chars[j - 1] {
+                let fresh = h[i - 1][j - 1] + FZF_SCORE_MATCH as i64 + bonus[j - 1] as i64;
+                let extended = m_score[i - 1][j - 1] + FZF_SCORE_MATCH as i64 + FZF_BONUS_CONSECUTIVE as i64;
+                m_score[i][j] = max(fresh, extended);
+            }
+
+            let skip = h[i][j - 1] - FZF_GAP_PENALTY as i64;
+
+            if m_score[i][j] >= skip {
+                h[i][j] = m_score[i][j];
+                matched_here[i][j] = true;
+            } else {
+                h[i][j] = skip;
+            }
+        }
+    }
+
+    if h[n][m] <= NEG_INFINITY / 2 {
+        return None;
+    }
+
+    let mut positions = Vec::with_capacity(n);
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        if matched_here[i][j] {
+            positions.push(cand_byte_offsets[j - 1]);
+            i -= 1;
+            j -= 1;
+        } else {
+            j -= 1;
+        }
+    }
+    positions.reverse();
+
+    Some((h[n][m] as i32, positions))
+}
+
+/// fzf-style subsequence scoring algorithm. Unlike the other algorithms in this module, `needle` is not expected to
+/// share most of its characters with `candidate` - instead it rewards `candidate`s where `needle`'s characters
+/// appear in order, preferring word-starting and consecutive matches. See [`fzf_match`](fzf_match) if the matched
+/// positions are needed (e.g. for highlighting) as well as the score.
+// Add a hidden, unused param to prevent direct construction.
+pub struct FzfScore(bool);
+impl SimilarityAlgorithm for FzfScore {
+    fn new() -> FzfScore {
+        FzfScore(false)
+    }
+
+    fn get_similarity(&mut self, a: &str, b: &str) -> f32 {
+        if a == b {
+            1f32
+        } else if a.len() == 1 && b.len() == 1 {
+            0f32
+        } else if a.is_empty() || b.is_empty() {
+            0f32
+        } else {
+            match fzf_match(a, b) {
+                Some((score, _)) => {
+                    let needle_len = a.chars().count() as i32;
+                    let max_score = needle_len * (FZF_SCORE_MATCH + FZF_BONUS_BOUNDARY * FZF_BONUS_FIRST_CHAR_MULTIPLIER);
+
+                    round_score_decimal((score as f32 / max_score as f32).min(1f32).max(0f32))
+                }
+                None => 0f32,
+            }
         }
     }
 }
@@ -208,6 +598,135 @@ mod test {
         assert_eq!(0.71429f32, inst.get_similarity("chance", "enhance"));
     }
 
+    // Regression test: the matrix used to be sized and iterated off byte length instead of char count, which panicked
+    // with an out-of-bounds index on any multi-byte input whose char count differed from its byte length.
+    #[test]
+    fn test_levenshtein_multibyte() {
+        assert_eq!(0.75f32, Levenshtein::new().get_similarity("café", "cafe"));
+    }
+
+    #[test]
+    fn test_osa_eq_strs() {
+        assert_eq!(1f32, OptimalStringAlignment::new().get_similarity("string", "string"));
+    }
+
+    #[test]
+    fn test_osa_one_char() {
+        assert_eq!(0f32, OptimalStringAlignment::new().get_similarity("a", "b"));
+    }
+
+    #[test]
+    fn test_osa_empty_str() {
+        assert_eq!(0f32, OptimalStringAlignment::new().get_similarity("string", ""));
+    }
+
+    #[test]
+    fn test_osa_correctness() {
+        let mut inst = OptimalStringAlignment::new();
+        assert_eq!(0.75f32, inst.get_similarity("rust", "bust"));
+        assert_eq!(0.25f32, inst.get_similarity("rust", "ritz"));
+        assert_eq!(0.71429f32, inst.get_similarity("chance", "enhance"));
+    }
+
+    #[test]
+    fn test_osa_transposition() {
+        let mut inst = OptimalStringAlignment::new();
+        assert_eq!(0.5f32, inst.get_similarity("ab", "ba"));
+        assert_eq!(0.85714f32, inst.get_similarity("recieve", "receive"));
+    }
+
+    // Regression test: the matrix used to be sized off byte length instead of char count, which panicked with an
+    // out-of-bounds index on any multi-byte input whose char count differed from its byte length.
+    #[test]
+    fn test_osa_multibyte() {
+        assert_eq!(0.75f32, OptimalStringAlignment::new().get_similarity("café", "cafe"));
+    }
+
+    #[test]
+    fn test_jaro_eq_strs() {
+        assert_eq!(1f32, Jaro::new().get_similarity("string", "string"));
+    }
+
+    #[test]
+    fn test_jaro_one_char() {
+        assert_eq!(0f32, Jaro::new().get_similarity("a", "b"));
+    }
+
+    #[test]
+    fn test_jaro_empty_str() {
+        assert_eq!(0f32, Jaro::new().get_similarity("string", ""));
+    }
+
+    #[test]
+    fn test_jaro_correctness() {
+        let mut inst = Jaro::new();
+        assert_eq!(0.94444f32, inst.get_similarity("MARTHA", "MARHTA"));
+        assert_eq!(0.82222f32, inst.get_similarity("DWAYNE", "DUANE"));
+    }
+
+    #[test]
+    fn test_jaro_winkler_eq_strs() {
+        assert_eq!(1f32, JaroWinkler::new().get_similarity("string", "string"));
+    }
+
+    #[test]
+    fn test_jaro_winkler_one_char() {
+        assert_eq!(0f32, JaroWinkler::new().get_similarity("a", "b"));
+    }
+
+    #[test]
+    fn test_jaro_winkler_empty_str() {
+        assert_eq!(0f32, JaroWinkler::new().get_similarity("string", ""));
+    }
+
+    #[test]
+    fn test_jaro_winkler_correctness() {
+        let mut inst = JaroWinkler::new();
+        assert_eq!(0.96111f32, inst.get_similarity("MARTHA", "MARHTA"));
+        assert_eq!(0.84f32, inst.get_similarity("DWAYNE", "DUANE"));
+    }
+
+    #[test]
+    fn test_fzf_match_positions() {
+        let (score, positions) = fzf_match("fb", "foo_bar").unwrap();
+        assert_eq!(vec![0, 4], positions);
+        assert!(score > 0);
+    }
+
+    #[test]
+    fn test_fzf_match_no_subsequence() {
+        assert_eq!(None, fzf_match("xyz", "foobar"));
+    }
+
+    #[test]
+    fn test_fzf_match_prefers_word_boundaries() {
+        // "fb" matches both candidates, but `foo_bar` lines `f` and `b` up with word starts while `afxb` buries
+        // both matches mid-word, so the former should score higher.
+        let (boundary_score, _) = fzf_match("fb", "foo_bar").unwrap();
+        let (buried_score, _) = fzf_match("fb", "afxb").unwrap();
+        assert!(boundary_score > buried_score);
+    }
+
+    #[test]
+    fn test_fzf_score_eq_strs() {
+        assert_eq!(1f32, FzfScore::new().get_similarity("string", "string"));
+    }
+
+    #[test]
+    fn test_fzf_score_one_char() {
+        assert_eq!(0f32, FzfScore::new().get_similarity("a", "b"));
+    }
+
+    #[test]
+    fn test_fzf_score_empty_str() {
+        assert_eq!(0f32, FzfScore::new().get_similarity("string", ""));
+    }
+
+    #[test]
+    fn test_fzf_score_no_subsequence() {
+        assert_eq!(0f32, FzfScore::new().get_similarity("xyz", "foobar"));
+    }
+
     #[cfg(feature = "nightly")]
     mod bench {
         use super::super::*;
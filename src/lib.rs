@@ -9,9 +9,21 @@
 extern crate sliding_windows;
 #[cfg(all(feature = "nightly", test))] extern crate test;
 
+use std::collections::HashSet;
+
 pub mod algorithms;
 pub(crate) mod util;
 
+/// Configuration for [`fuzzy_match_with_config`](fuzzy_match_with_config), controlling how needle and haystack
+/// strings are folded before being handed to the similarity algorithms.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MatchConfig {
+    /// Fold case differences (e.g. `"Rust"` vs `"rust"`) before scoring.
+    pub ignore_case: bool,
+    /// Fold common Latin diacritics to their base ASCII letter (e.g. `"café"` vs `"cafe"`) before scoring.
+    pub normalize: bool,
+}
+
 /// Fuzzy finds a set of string-item pairs using a Sorensen Dice coefficient and Levenshtein for breaking ties. May
 /// return None if no match is similar. This consumes the input vector. See
 /// [`fuzzy_match_with_algorithms`](fuzzy_match::fuzzy_match_with_algorithms) for additional details.
@@ -46,19 +58,59 @@ pub fn fuzzy_match<T>(needle: &str, haystack: Vec<(&str, T)>) -> Option<T> {
 /// # Panics
 /// This function will panic if the haystack is empty (length 0).
 pub fn fuzzy_match_with_algorithms<T, FST: algorithms::SimilarityAlgorithm, SND: algorithms::SimilarityAlgorithm>(
+    needle: &str,
+    haystack: Vec<(&str, T)>,
+) -> Option<T> {
+    fuzzy_match_with_algorithms_prefiltered::<T, FST, SND>(needle, haystack, false)
+}
+
+/// Version of [`fuzzy_match_with_algorithms`](fuzzy_match_with_algorithms) which allows enabling a cheap
+/// character-presence prefilter pass that skips candidates which can't possibly beat the best match found so far on
+/// large haystacks. A candidate's Sorensen-Dice-style upper bound - the fraction of `needle`'s distinct characters it
+/// shares, `|needle_chars ∩ candidate_chars| / |needle_chars|` - is compared against the running `highest_weight`,
+/// and the candidate is skipped without running `FST`/`SND` over it at all if its bound is below `highest_weight`.
+///
+/// This is only safe to enable for algorithms whose score can never exceed that character-overlap bound - true of
+/// every algorithm shipped in [`algorithms`](algorithms), but not something
+/// [`SimilarityAlgorithm`](algorithms::SimilarityAlgorithm) requires of caller-supplied `FST`/`SND`. Passing
+/// `prefilter: true` with a custom algorithm that doesn't uphold that invariant can change the winner. When in
+/// doubt, leave this `false`.
+///
+/// # Panics
+/// This function will panic if the haystack is empty (length 0).
+pub fn fuzzy_match_with_algorithms_prefiltered<
+    T,
+    FST: algorithms::SimilarityAlgorithm,
+    SND: algorithms::SimilarityAlgorithm,
+>(
     needle: &str,
     mut haystack: Vec<(&str, T)>,
+    prefilter: bool,
 ) -> Option<T> {
     if haystack.len() == 0 {
         panic!("No haystack provided!");
     }
 
+    let needle_chars: HashSet<char> = needle.chars().collect();
+
     let mut highest_set: Vec<(&str, T)> = Vec::new();
     let mut highest_weight = 0f32;
     let mut first_algo = FST::new();
 
     // Try with first-case algorithm.
     for (name, item) in haystack.drain(..) {
+        if prefilter && highest_weight > 0f32 {
+            let candidate_chars: HashSet<char> = name.chars().collect();
+            let shared = needle_chars.intersection(&candidate_chars).count();
+            let upper_bound = shared as f32 / needle_chars.len() as f32;
+
+            // `name` can't possibly score higher than its character-overlap upper bound, so if that bound is below
+            // what we already have, `name` can neither win nor tie and there's no point running the real algorithm.
+            if upper_bound < highest_weight {
+                continue;
+            }
+        }
+
         let weight = first_algo.get_similarity(needle, name);
         if weight == highest_weight {
             highest_set.push((name, item))
@@ -99,3 +151,259 @@ pub fn fuzzy_match_with_algorithms<T, FST: algorithms::SimilarityAlgorithm, SND:
         Some(item)
     }
 }
+
+/// Version of [`fuzzy_match`](fuzzy_match::fuzzy_match) which folds the needle and haystack according to `config`
+/// (case folding and/or diacritic normalization) before matching with Sorensen-Dice and Levenshtein. This consumes
+/// the input vector. See [`fuzzy_match_with_config_and_algorithms`](fuzzy_match::fuzzy_match_with_config_and_algorithms)
+/// for overriding the algorithms used as well.
+///
+/// # Examples
+/// ```rust
+/// use fuzzy_match::{fuzzy_match_with_config, MatchConfig};
+///
+/// let haystack = vec![("Rust", 0), ("Java", 1), ("Lisp", 2)];
+/// let config = MatchConfig { ignore_case: true, normalize: false };
+/// assert_eq!(Some(0), fuzzy_match_with_config("rust", haystack, config));
+/// ```
+///
+/// # Panics
+/// This function will panic if the haystack is empty (length 0).
+pub fn fuzzy_match_with_config<T>(needle: &str, haystack: Vec<(&str, T)>, config: MatchConfig) -> Option<T> {
+    fuzzy_match_with_config_and_algorithms::<T, algorithms::SorensenDice, algorithms::Levenshtein>(
+        needle, haystack, config,
+    )
+}
+
+/// Version of [`fuzzy_match_with_config`](fuzzy_match_with_config) which additionally allows overriding the first
+/// and second choice algorithms, instead of using Sorensen-Dice and Levenshtein respectively. This consumes the
+/// input vector.
+///
+/// # Panics
+/// This function will panic if the haystack is empty (length 0).
+pub fn fuzzy_match_with_config_and_algorithms<
+    T,
+    FST: algorithms::SimilarityAlgorithm,
+    SND: algorithms::SimilarityAlgorithm,
+>(
+    needle: &str,
+    haystack: Vec<(&str, T)>,
+    config: MatchConfig,
+) -> Option<T> {
+    if !config.ignore_case && !config.normalize {
+        return fuzzy_match_with_algorithms::<T, FST, SND>(needle, haystack);
+    }
+
+    let folded_needle = util::fold_str(needle, &config);
+
+    let (folded_names, items): (Vec<String>, Vec<T>) = haystack
+        .into_iter()
+        .map(|(name, item)| (util::fold_str(name, &config), item))
+        .unzip();
+    let folded_haystack: Vec<(&str, T)> = folded_names.iter().map(|name| name.as_str()).zip(items).collect();
+
+    fuzzy_match_with_algorithms::<T, FST, SND>(&folded_needle, folded_haystack)
+}
+
+/// Finds the best `n` matches from a set of string-item pairs using a Sorensen Dice coefficient and Levenshtein for
+/// breaking ties, returning each matched item alongside its Sorensen Dice weight. Unlike
+/// [`fuzzy_match`](fuzzy_match::fuzzy_match), this never discards tied candidates - ties are broken with
+/// Levenshtein as a stable sub-sort key instead of being dropped. This consumes the input vector. See
+/// [`fuzzy_match_n_with_algorithms`](fuzzy_match::fuzzy_match_n_with_algorithms) for additional details.
+///
+/// Returns an empty `Vec` (rather than panicking) if the haystack is empty.
+///
+/// # Examples
+/// ```rust
+/// use fuzzy_match::fuzzy_match_n;
+///
+/// let haystack = vec![("rust", 0), ("java", 1), ("lisp", 2)];
+/// assert_eq!(vec![(0, 1.0)], fuzzy_match_n("rust", haystack, 1, None));
+/// ```
+pub fn fuzzy_match_n<T>(needle: &str, haystack: Vec<(&str, T)>, n: usize, min_score: Option<f32>) -> Vec<(T, f32)> {
+    fuzzy_match_n_with_algorithms::<T, algorithms::SorensenDice, algorithms::Levenshtein>(
+        needle, haystack, n, min_score,
+    )
+}
+
+/// Version of [`fuzzy_match_n`](fuzzy_match_n) which allows overriding the first and second choice algorithms,
+/// instead of using Sorensen-Dice and Levenshtein respectively. This consumes the input vector.
+///
+/// Every candidate is scored with `FST`; candidates scoring below `min_score` (if given) are discarded. The
+/// remainder are sorted by descending `FST` score, breaking ties with `SND` as a stable sub-sort key, and the best
+/// `n` are returned together with their `FST` weight.
+pub fn fuzzy_match_n_with_algorithms<T, FST: algorithms::SimilarityAlgorithm, SND: algorithms::SimilarityAlgorithm>(
+    needle: &str,
+    mut haystack: Vec<(&str, T)>,
+    n: usize,
+    min_score: Option<f32>,
+) -> Vec<(T, f32)> {
+    use std::cmp::Ordering;
+
+    if haystack.is_empty() || n == 0 {
+        return Vec::new();
+    }
+
+    let mut first_algo = FST::new();
+    let mut second_algo = SND::new();
+
+    let mut scored: Vec<(T, f32, f32)> = haystack
+        .drain(..)
+        .map(|(name, item)| {
+            let primary = first_algo.get_similarity(needle, name);
+            let secondary = second_algo.get_similarity(needle, name);
+            (item, primary, secondary)
+        })
+        .filter(|&(_, primary, _)| min_score.map_or(true, |min| primary >= min))
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal))
+    });
+
+    scored.truncate(n);
+    scored.into_iter().map(|(item, primary, _)| (item, primary)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algorithms::{Levenshtein, SimilarityAlgorithm, SorensenDice};
+
+    // A deliberately pathological algorithm whose score doesn't depend on character overlap with the needle at
+    // all - the kind of `SimilarityAlgorithm` the character-presence prefilter is not safe to assume away.
+    struct ConstantByLength(bool);
+    impl SimilarityAlgorithm for ConstantByLength {
+        fn new() -> ConstantByLength {
+            ConstantByLength(false)
+        }
+
+        fn get_similarity(&mut self, _a: &str, b: &str) -> f32 {
+            1f32 / b.len() as f32
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_match_with_algorithms_defaults_prefilter_off() {
+        // "xyz" shares no characters with "needle", but under `ConstantByLength` it's the best-scoring candidate
+        // (shortest string). The default (non-prefiltered) entry point must still find it.
+        let haystack = vec![("needle", 1), ("xyz", 2)];
+        assert_eq!(
+            Some(2),
+            fuzzy_match_with_algorithms::<_, ConstantByLength, ConstantByLength>("needle", haystack)
+        );
+    }
+
+    #[test]
+    fn test_prefilter_can_change_winner_for_unsafe_algorithms() {
+        // Demonstrates why `prefilter: true` is opt-in: for an algorithm that violates the
+        // "no character overlap implies score 0.0" invariant, enabling the prefilter picks a different (wrong)
+        // winner than leaving it disabled.
+        let with_prefilter = fuzzy_match_with_algorithms_prefiltered::<_, ConstantByLength, ConstantByLength>(
+            "needle",
+            vec![("needled", 1), ("xyz", 2)],
+            true,
+        );
+        let without_prefilter = fuzzy_match_with_algorithms_prefiltered::<_, ConstantByLength, ConstantByLength>(
+            "needle",
+            vec![("needled", 1), ("xyz", 2)],
+            false,
+        );
+
+        assert_eq!(Some(2), without_prefilter);
+        assert_ne!(with_prefilter, without_prefilter);
+    }
+
+    #[test]
+    fn test_prefilter_matches_unfiltered_winner_for_shipped_algorithms() {
+        let haystack = || {
+            vec![
+                ("rust", 0),
+                ("bust", 1),
+                ("crustacean", 2),
+                ("completely unrelated", 3),
+                ("xyz", 4),
+            ]
+        };
+
+        let with_prefilter = fuzzy_match_with_algorithms_prefiltered::<_, SorensenDice, Levenshtein>(
+            "rust", haystack(), true,
+        );
+        let without_prefilter = fuzzy_match_with_algorithms_prefiltered::<_, SorensenDice, Levenshtein>(
+            "rust", haystack(), false,
+        );
+
+        assert_eq!(without_prefilter, with_prefilter);
+    }
+
+    #[test]
+    fn test_fuzzy_match_n_empty_haystack() {
+        let haystack: Vec<(&str, i32)> = Vec::new();
+        assert_eq!(Vec::<(i32, f32)>::new(), fuzzy_match_n("rust", haystack, 3, None));
+    }
+
+    #[test]
+    fn test_fuzzy_match_n_min_score_filters_low_scores() {
+        let haystack = vec![("rust", 0), ("bust", 1), ("completely unrelated", 2)];
+        let results = fuzzy_match_n("rust", haystack, 3, Some(0.5));
+
+        assert_eq!(2, results.len());
+        assert!(results.iter().all(|&(_, score)| score >= 0.5));
+    }
+
+    #[test]
+    fn test_fuzzy_match_n_breaks_ties_with_secondary_algorithm() {
+        // `ConstantScore` ties every candidate on the primary pass, so the final order must come entirely from
+        // `ConstantByLength` (the secondary, tie-breaking algorithm), which scores "short" above "longerstring".
+        struct ConstantScore(bool);
+        impl SimilarityAlgorithm for ConstantScore {
+            fn new() -> ConstantScore {
+                ConstantScore(false)
+            }
+
+            fn get_similarity(&mut self, _a: &str, _b: &str) -> f32 {
+                0.5f32
+            }
+        }
+
+        let haystack = vec![("longerstring", 0), ("short", 1)];
+        let results =
+            fuzzy_match_n_with_algorithms::<_, ConstantScore, ConstantByLength>("needle", haystack, 2, None);
+
+        assert_eq!(2, results.len());
+        assert_eq!(1, results[0].0);
+        assert_eq!(0, results[1].0);
+    }
+
+    #[test]
+    fn test_fuzzy_match_n_sorted_descending_and_truncated() {
+        let haystack = vec![("rust", 0), ("bust", 1), ("crustacean", 2), ("xyz", 3)];
+        let results = fuzzy_match_n("rust", haystack, 2, None);
+
+        assert_eq!(2, results.len());
+        assert!(results[0].1 >= results[1].1);
+        assert_eq!(0, results[0].0);
+    }
+
+    #[test]
+    fn test_fuzzy_match_with_config_folds_case() {
+        let haystack = vec![("Rust", 0), ("Java", 1)];
+        let config = MatchConfig { ignore_case: true, normalize: false };
+        assert_eq!(Some(0), fuzzy_match_with_config("rust", haystack, config));
+    }
+
+    #[test]
+    fn test_fuzzy_match_with_config_folds_diacritics() {
+        let haystack = vec![("café", 0), ("tea", 1)];
+        let config = MatchConfig { ignore_case: false, normalize: true };
+        assert_eq!(Some(0), fuzzy_match_with_config("cafe", haystack, config));
+    }
+
+    #[test]
+    fn test_fuzzy_match_with_config_no_folding_is_unchanged() {
+        let haystack = vec![("Rust", 0), ("rust", 1)];
+        let config = MatchConfig::default();
+        assert_eq!(Some(1), fuzzy_match_with_config("rust", haystack, config));
+    }
+}
@@ -1,5 +1,92 @@
 //! Crate-wide utility functions.
 
+use MatchConfig;
+
 pub(crate) fn round_score_decimal(val: f32) -> f32 {
     (val * 100_000f32).round() / 100_000f32
 }
+
+/// Folds every character in `s` according to `config`. Used to apply [`MatchConfig`](MatchConfig) uniformly right
+/// before the similarity algorithms build their bigrams/char vectors.
+pub(crate) fn fold_str(s: &str, config: &MatchConfig) -> String {
+    s.chars().map(|c| fold_char(c, config)).collect()
+}
+
+fn fold_char(c: char, config: &MatchConfig) -> char {
+    let c = if config.normalize { normalize_char(c) } else { c };
+
+    if config.ignore_case {
+        fold_case_char(c)
+    } else {
+        c
+    }
+}
+
+// Simple case folding: an ASCII fast path, falling back to `char::to_lowercase` for the rest of Unicode.
+fn fold_case_char(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+// Folds common Latin-1 diacritics to their base ASCII letter.
+fn normalize_char(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ç' => 'c',
+        'Ç' => 'C',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fold_str_no_op_by_default() {
+        let config = MatchConfig::default();
+        assert_eq!("Café", fold_str("Café", &config));
+    }
+
+    #[test]
+    fn test_fold_str_ignore_case_only() {
+        let config = MatchConfig { ignore_case: true, normalize: false };
+        assert_eq!("café", fold_str("CAFÉ", &config));
+    }
+
+    #[test]
+    fn test_fold_str_normalize_only() {
+        let config = MatchConfig { ignore_case: false, normalize: true };
+        assert_eq!("cafe", fold_str("café", &config));
+        assert_eq!("CAFE", fold_str("CAFÉ", &config));
+    }
+
+    #[test]
+    fn test_fold_str_case_and_diacritics_together() {
+        let config = MatchConfig { ignore_case: true, normalize: true };
+        assert_eq!("cafe", fold_str("CAFÉ", &config));
+        assert_eq!("uber", fold_str("Über", &config));
+    }
+
+    #[test]
+    fn test_fold_str_empty() {
+        let config = MatchConfig { ignore_case: true, normalize: true };
+        assert_eq!("", fold_str("", &config));
+    }
+}